@@ -1,33 +1,61 @@
 use anyhow::Context;
+use async_trait::async_trait;
+use axum::{
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    routing::post,
+    Json, Router,
+};
 use chrono::Local;
-use clap::Parser;
+use clap::{ArgGroup, Parser, ValueEnum};
 use gitlab::{
-    api::{projects::merge_requests, AsyncQuery},
-    AsyncGitlab, Gitlab, MergeRequest,
+    api::{groups, projects::merge_requests, AsyncQuery},
+    AsyncGitlab, Gitlab, MergeRequest, Project,
 };
 use reqwest::Url;
 use secrecy::{ExposeSecret, SecretString};
+use serde::Deserialize;
 use slack_morphism::prelude::*;
+use std::{
+    collections::HashMap,
+    net::SocketAddr,
+    path::PathBuf,
+    sync::Arc,
+    time::Duration,
+};
 use tracing::{debug, info_span, trace};
 use tracing_subscriber::{prelude::*, EnvFilter};
 
-/// Notifier to send a Slack Webhook if open Merge Requests exist for a
+/// Notifier to send a chat Webhook if open Merge Requests exist for a
 /// particular Gitlab project.
 ///
 /// This is a small one-shot utility that will check whether the specified
 /// Gitlab has any open Merge Requests, with the option to filter them to a
 /// specific target branch.
 ///
-/// The general use case for this is to nag a Slack channel if there is an open
+/// The general use case for this is to nag a chat channel if there is an open
 /// MR to production at a certain time during the day. To setup this behaviour,
-/// the binary should be run under Cron, and given a Slack webhook URL to notify
-/// whenever an MR is open.
+/// the binary should be run under Cron, and given a Slack or Mattermost webhook
+/// URL to notify whenever an MR is open.
+///
+/// With `--gitlab-group-id`, every project in a group is checked instead of a
+/// single project. With `--serve`, the binary instead runs as a long-lived
+/// server reacting to GitLab merge request webhook events.
 #[derive(Debug, Parser)]
 #[command(author, version)]
+#[command(group(ArgGroup::new("scope").args(["gitlab_project_id", "gitlab_group_id"])))]
 struct CmdArgs {
-    /// Optional Webhook URL to notify if open merge requests are found
-    #[arg(short, long, env)]
-    slack_webhook_url: Option<Url>,
+    /// Optional Webhook URL to notify if open merge requests are found. Both
+    /// Slack and Mattermost incoming webhooks are supported; see
+    /// `--chat-backend`. Accepts `--slack-webhook-url` as a back-compat alias
+    /// from before Mattermost support existed.
+    #[arg(short = 's', long, env, alias = "slack-webhook-url")]
+    chat_webhook_url: Option<Url>,
+    /// Chat backend to render messages for. If unset, it is inferred from the
+    /// webhook URL (a `slack.com` host selects Slack, anything else
+    /// Mattermost).
+    #[arg(long, env, value_enum)]
+    chat_backend: Option<ChatBackend>,
     /// Gitlab token which requires read:api access to the project in question
     #[arg(short = 't', long, env)]
     gitlab_token: SecretString,
@@ -35,9 +63,18 @@ struct CmdArgs {
     /// assumed by default.
     #[arg(short, long, env)]
     gitlab_host: String,
-    /// Numeric Gitlab project ID of the project to check
+    /// Numeric Gitlab project ID of the project to check. Mutually exclusive
+    /// with `--gitlab-group-id`. One of the two is required unless `--serve`
+    /// is set, in which case the target project is taken from each incoming
+    /// webhook event instead.
     #[arg(short = 'i', long, env)]
-    gitlab_project_id: u64,
+    gitlab_project_id: Option<u64>,
+    /// Numeric Gitlab group ID to check. When set, open merge requests across
+    /// every project in the group are enumerated in a single run. Mutually
+    /// exclusive with `--gitlab-project-id`. One of the two is required
+    /// unless `--serve` is set.
+    #[arg(short = 'G', long, env, conflicts_with = "gitlab_project_id")]
+    gitlab_group_id: Option<u64>,
     /// Optional branch to filter for - if specified, only merge requests with a
     /// target of this specific branch will trigger the notification.
     #[arg(short = 'T', long, env)]
@@ -47,11 +84,255 @@ struct CmdArgs {
     /// execution interval is insufficient.
     #[arg(short = 'd', long, env)]
     min_dwell_secs: Option<i64>,
+    /// Specify a minimum age (since creation) an MR must have reached before
+    /// creating a notification, regardless of later update activity. Unlike
+    /// `--min-dwell-secs`, this soak window is not reset by bot comments or
+    /// other updates, giving authors a grace period to self-merge before
+    /// being nagged at.
+    #[arg(short = 'a', long, env)]
+    min_age_secs: Option<i64>,
+    /// Emit a single aggregated digest message (one header plus one section
+    /// per MR) instead of posting a separate webhook message for every MR.
+    /// The per-message mode remains the default for backward compatibility.
+    #[arg(long, default_value_t = false)]
+    digest: bool,
+    /// Path to a file mapping GitLab usernames to Slack user IDs, one
+    /// `gitlab_user=slack_id` pair per line, so rendered messages can
+    /// `@`-mention the responsible person directly. Usernames without a
+    /// mapping fall back to their plain GitLab username.
+    #[arg(long, env)]
+    user_map: Option<PathBuf>,
+    /// Only nag about merge requests that are blocked by merge conflicts
+    /// (GitLab's `has_conflicts` flag) and require author action,
+    /// rather than every MR merely waiting on a reviewer.
+    #[arg(long, default_value_t = false)]
+    only_blocked: bool,
+    /// Run as a long-lived HTTP server reacting to GitLab merge request
+    /// webhook events instead of polling on a schedule. Bind address to
+    /// listen on, e.g. `0.0.0.0:8080`. When set, `mr-nag` never exits on its
+    /// own; register this address under the project or group's Webhooks
+    /// settings in GitLab, pointed at `/webhook`.
+    #[arg(long, env)]
+    serve: Option<SocketAddr>,
+    /// Shared secret GitLab sends in the `X-Gitlab-Token` header of every
+    /// webhook request; required when `--serve` is set. Configure the same
+    /// value as the webhook's secret token in GitLab.
+    #[arg(long, env)]
+    webhook_secret: Option<SecretString>,
+    /// When `--serve` is set, re-check an opened MR this long after it first
+    /// appears and nag again if it is still open. Covers MRs that are opened
+    /// and then never touched again, so no further GitLab event would
+    /// otherwise fire.
+    #[arg(long, env, default_value_t = 86400)]
+    still_open_after_secs: u64,
     /// Enable verbose logging
     #[arg(short = 'v', long, default_value_t = false)]
     verbose: bool,
 }
 
+/// The chat service a webhook URL points at. Slack and Mattermost share a
+/// near-identical incoming-webhook payload (`text` plus attachments), so the
+/// same rendered [`ChatMessage`] can be adapted to either.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum ChatBackend {
+    Slack,
+    Mattermost,
+}
+
+impl ChatBackend {
+    /// Guess the backend from a webhook URL, defaulting to Mattermost for any
+    /// host which isn't obviously Slack.
+    fn infer(url: &Url) -> Self {
+        match url.host_str() {
+            Some(host) if host.ends_with("slack.com") => ChatBackend::Slack,
+            _ => ChatBackend::Mattermost,
+        }
+    }
+}
+
+/// Maps GitLab usernames to Slack user IDs, so rendered messages can
+/// `@`-mention the responsible person directly instead of printing their
+/// plain GitLab username.
+#[derive(Debug, Default, Clone)]
+struct UserMap(HashMap<String, String>);
+
+impl UserMap {
+    /// Load a `gitlab_user=slack_id` pair-per-line mapping file. Blank lines
+    /// and lines starting with `#` are ignored.
+    fn load(path: &std::path::Path) -> anyhow::Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read user map {path:?}"))?;
+        let mut map = HashMap::new();
+        for line in contents.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (gitlab_user, slack_id) = line
+                .split_once('=')
+                .with_context(|| format!("invalid user-map line: {line:?}"))?;
+            map.insert(gitlab_user.trim().to_string(), slack_id.trim().to_string());
+        }
+        Ok(UserMap(map))
+    }
+
+    /// Render a GitLab username as a mention for the given backend. The
+    /// mapping file stores Slack user IDs, so the `<@id>` form only makes
+    /// sense when actually posting to Slack; any other backend (and any
+    /// unmapped username) falls back to a plain `@username` mention.
+    fn mention(&self, gitlab_username: &str, backend: ChatBackend) -> String {
+        match (backend, self.0.get(gitlab_username)) {
+            (ChatBackend::Slack, Some(slack_id)) => format!("<@{slack_id}>"),
+            _ => format!("@{gitlab_username}"),
+        }
+    }
+}
+
+/// A backend-agnostic rendered notification. Holds the plain-text fallback
+/// along with an optional markdown rendering and a primary link. Each
+/// [`ChatNotifier`] adapts the text, markdown and sections to its own payload
+/// format; neither notifier sends `link` on its own.
+struct ChatMessage {
+    text: String,
+    markdown: Option<String>,
+    /// `MergeRequest::web_url` is a plain `String` in the `gitlab` crate, not
+    /// a `Url` — keep this a `String` too rather than parsing a URL we never
+    /// need to manipulate.
+    link: Option<String>,
+    /// Additional markdown blocks rendered after `markdown`; each becomes its
+    /// own Slack section block, and they are joined with blank lines for
+    /// Mattermost. Used by digest mode to list one MR per block.
+    sections: Vec<String>,
+}
+
+impl ChatMessage {
+    /// A plain message with just a text fallback and optional markdown body.
+    fn new(text: String, markdown: Option<String>, link: Option<String>) -> Self {
+        ChatMessage {
+            text,
+            markdown,
+            link,
+            sections: Vec::new(),
+        }
+    }
+}
+
+/// A chat service capable of posting one or more rendered messages.
+#[async_trait]
+trait ChatNotifier {
+    async fn post(&self, messages: &[ChatMessage]) -> anyhow::Result<()>;
+}
+
+/// Posts messages to a Slack incoming webhook via `slack_morphism`.
+struct SlackNotifier {
+    webhook_url: Url,
+}
+
+#[async_trait]
+impl ChatNotifier for SlackNotifier {
+    async fn post(&self, messages: &[ChatMessage]) -> anyhow::Result<()> {
+        let _slack_span = info_span!("slack_webhook_post").entered();
+        let client = SlackClient::new(SlackClientHyperConnector::new());
+        for message in messages {
+            let mut content = SlackMessageContent::new().with_text(message.text.clone());
+            let mut blocks: Vec<SlackBlock> = Vec::new();
+            if let Some(markdown) = &message.markdown {
+                blocks.push(SlackSectionBlock::new().with_text(md!(markdown.clone())).into());
+            }
+            for section in &message.sections {
+                blocks.push(SlackSectionBlock::new().with_text(md!(section.clone())).into());
+            }
+            if !blocks.is_empty() {
+                content = content.with_blocks(blocks);
+            }
+            client
+                .post_webhook_message(
+                    &self.webhook_url,
+                    &SlackApiPostWebhookMessageRequest::new(content),
+                )
+                .await
+                .context("failed to post Slack webhook message")?;
+        }
+        Ok(())
+    }
+}
+
+/// Posts messages to a Mattermost incoming webhook. Mattermost renders the
+/// `text` field as markdown, so the markdown rendering is preferred where
+/// present.
+struct MattermostNotifier {
+    webhook_url: Url,
+    client: reqwest::Client,
+}
+
+impl MattermostNotifier {
+    fn new(webhook_url: Url) -> Self {
+        MattermostNotifier {
+            webhook_url,
+            client: reqwest::Client::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl ChatNotifier for MattermostNotifier {
+    async fn post(&self, messages: &[ChatMessage]) -> anyhow::Result<()> {
+        let _mm_span = info_span!("mattermost_webhook_post").entered();
+        for message in messages {
+            let mut body = message.markdown.clone().unwrap_or_else(|| message.text.clone());
+            for section in &message.sections {
+                body.push_str("\n\n");
+                body.push_str(section);
+            }
+            let text = &body;
+            self.client
+                .post(self.webhook_url.clone())
+                .json(&serde_json::json!({ "text": text }))
+                .send()
+                .await
+                .context("failed to post Mattermost webhook message")?
+                .error_for_status()
+                .context("Mattermost webhook returned an error status")?;
+        }
+        Ok(())
+    }
+}
+
+/// Build the configured [`ChatNotifier`] and the backend it was built for,
+/// if a webhook URL was supplied. The backend is needed alongside the
+/// notifier wherever a [`UserMap`] mention is rendered, since the mention
+/// syntax differs between Slack and Mattermost.
+fn build_notifier(args: &CmdArgs) -> Option<(ChatBackend, Box<dyn ChatNotifier>)> {
+    let url = args.chat_webhook_url.clone()?;
+    let backend = args.chat_backend.unwrap_or_else(|| ChatBackend::infer(&url));
+    debug!(?backend, "selected chat backend");
+    let notifier: Box<dyn ChatNotifier> = match backend {
+        ChatBackend::Slack => Box::new(SlackNotifier { webhook_url: url }),
+        ChatBackend::Mattermost => Box::new(MattermostNotifier::new(url)),
+    };
+    Some((backend, notifier))
+}
+
+/// Query the open merge requests for a single project, optionally filtered
+/// to a target branch.
+async fn open_merge_requests(
+    gitlab: &AsyncGitlab,
+    project_id: u64,
+    target_branch: &str,
+) -> anyhow::Result<Vec<MergeRequest>> {
+    let mr_q = merge_requests::MergeRequests::builder()
+        .project(project_id)
+        .state(merge_requests::MergeRequestState::Opened)
+        .wip(false)
+        .target_branch(target_branch)
+        .build()
+        .unwrap();
+    mr_q
+        .query_async(gitlab)
+        .await
+        .with_context(|| format!("failed to query merge requests for project {project_id}"))
+}
+
 /// Get the merge requests as per the input args, filtering for project, state (open) and target branch (if specified)
 #[tracing::instrument(skip_all)]
 async fn get_merge_requsts<'a>(
@@ -59,20 +340,39 @@ async fn get_merge_requsts<'a>(
     gitlab: &'a AsyncGitlab,
 ) -> anyhow::Result<impl IntoIterator<Item = MergeRequest>> {
     let tb = args.target_branch.as_ref().map_or("", |x| &x);
-    debug!(
-        target_branch = tb,
-        project_id = args.gitlab_project_id,
-        "searching project for open MRs"
-    );
-    let mr_q = merge_requests::MergeRequests::builder()
-        .project(args.gitlab_project_id)
-        .state(merge_requests::MergeRequestState::Opened)
-        .wip(false)
-        .target_branch(tb)
-        .build()
-        .unwrap();
     // have to use let ... here to explicitly inform the type (Vec)
-    let merge_requests: Vec<MergeRequest> = mr_q.query_async(gitlab).await.unwrap();
+    let merge_requests: Vec<MergeRequest> = if let Some(group_id) = args.gitlab_group_id {
+        debug!(
+            target_branch = tb,
+            group_id, "enumerating group's projects for open MRs"
+        );
+        // The `gitlab` crate has no group-level merge-request listing
+        // endpoint, only a project-scoped one, so fall back to listing every
+        // project in the group and querying each project's open MRs
+        // individually.
+        let projects_q = groups::projects::GroupProjects::builder()
+            .group(group_id)
+            .build()
+            .unwrap();
+        let projects: Vec<Project> = gitlab::api::paged(projects_q, gitlab::api::Pagination::All)
+            .query_async(gitlab)
+            .await
+            .with_context(|| format!("failed to list projects for group {group_id}"))?;
+        let mut merge_requests = Vec::new();
+        for project in projects {
+            merge_requests.extend(open_merge_requests(gitlab, project.id, tb).await?);
+        }
+        merge_requests
+    } else {
+        let project_id = args
+            .gitlab_project_id
+            .expect("clap enforces one of project/group id is set");
+        debug!(
+            target_branch = tb,
+            project_id, "searching project for open MRs"
+        );
+        open_merge_requests(gitlab, project_id, tb).await?
+    };
     debug!(
         mr_in_scope = merge_requests.len(),
         "merge request query complete"
@@ -82,23 +382,396 @@ async fn get_merge_requsts<'a>(
 
 struct WrappedMR(MergeRequest);
 
+impl WrappedMR {
+    /// The `group/project` path this MR belongs to, derived from its
+    /// `web_url` (e.g. `.../group/project/-/merge_requests/123`). Useful when
+    /// scanning a whole group, so readers can tell which repo an MR is in.
+    fn project_path(&self) -> Option<String> {
+        self.0
+            .web_url
+            .split_once("/-/merge_requests/")
+            .map(|(path, _)| path.to_string())
+            .and_then(|path| Url::parse(&path).ok())
+            .map(|url| url.path().trim_start_matches('/').to_string())
+    }
+
+    /// The MR author and any assignees, rendered as mentions for the given
+    /// backend where the `--user-map` has an entry, falling back to the
+    /// plain GitLab username otherwise.
+    fn responsible(&self, user_map: &UserMap, backend: ChatBackend) -> String {
+        let author = user_map.mention(&self.0.author.username, backend);
+        let assignees: Vec<String> = self
+            .0
+            .assignees
+            .as_ref()
+            .map(|assignees| {
+                assignees
+                    .iter()
+                    .map(|u| user_map.mention(&u.username, backend))
+                    .collect()
+            })
+            .unwrap_or_default();
+        if assignees.is_empty() {
+            format!("opened by {author}")
+        } else {
+            format!("opened by {author}, assigned to {}", assignees.join(", "))
+        }
+    }
+
+    /// Whether GitLab reports this MR as blocked by merge conflicts, rather
+    /// than merely awaiting review/merge.
+    fn is_blocked(&self) -> bool {
+        self.0.has_conflicts
+    }
+
+    /// A link to the branch compare view (`target...source`) for this MR's
+    /// project, used to point authors straight at the conflicting diff.
+    fn compare_url(&self) -> Option<String> {
+        let (project_url, _) = self.0.web_url.split_once("/-/merge_requests/")?;
+        Some(format!(
+            "{project_url}/-/compare/{}...{}",
+            self.0.target_branch, self.0.source_branch
+        ))
+    }
+
+    /// Render this MR into a backend-agnostic [`ChatMessage`].
+    fn render(&self, user_map: &UserMap, backend: ChatBackend) -> ChatMessage {
+        let project = self.project_path();
+        let prefix = project
+            .as_ref()
+            .map_or_else(String::new, |p| format!("{p} "));
+        if self.is_blocked() {
+            let compare = self
+                .compare_url()
+                .map_or_else(String::new, |u| format!(" — [compare branches]({u})"));
+            return ChatMessage::new(
+                format!(
+                    "{}MR #{} needs rebase / has conflicts: \"{}\" ({})",
+                    prefix,
+                    self.0.id,
+                    self.0.title,
+                    self.responsible(user_map, backend)
+                ),
+                Some(format!(
+                    "{}:warning: MR **#{}: [{}]({})** needs rebase / has conflicts — {}{}",
+                    prefix,
+                    self.0.id,
+                    self.0.title,
+                    self.0.web_url,
+                    self.responsible(user_map, backend),
+                    compare
+                )),
+                Some(self.0.web_url.clone()),
+            );
+        }
+        ChatMessage::new(
+            format!(
+                "{}MR #{} awaiting merge: \"{}\" ({})",
+                prefix,
+                self.0.id,
+                self.0.title,
+                self.responsible(user_map, backend)
+            ),
+            Some(format!(
+                "{}MR **#{}: [{}]({})** — {}",
+                prefix,
+                self.0.id,
+                self.0.title,
+                self.0.web_url,
+                self.responsible(user_map, backend)
+            )),
+            Some(self.0.web_url.clone()),
+        )
+    }
+
+    /// Render this MR as a single digest line: title, link, author/assignees
+    /// and age, or — when blocked by merge conflicts — a distinct "needs
+    /// rebase" warning line with a compare-branches link instead. Used by
+    /// `--digest` mode to list one MR per section block rather than posting
+    /// a separate message for each.
+    fn render_digest_section(
+        &self,
+        now: chrono::DateTime<Local>,
+        user_map: &UserMap,
+        backend: ChatBackend,
+    ) -> String {
+        let project = self.project_path();
+        let prefix = project
+            .as_ref()
+            .map_or_else(String::new, |p| format!("{p} "));
+        if self.is_blocked() {
+            let compare = self
+                .compare_url()
+                .map_or_else(String::new, |u| format!(" — [compare branches]({u})"));
+            return format!(
+                "{}:warning: **[{}]({})** needs rebase / has conflicts — {}{}",
+                prefix,
+                self.0.title,
+                self.0.web_url,
+                self.responsible(user_map, backend),
+                compare
+            );
+        }
+        let age = format_age(now.signed_duration_since(self.0.created_at));
+        format!(
+            "{}**[{}]({})** — {}, {} old",
+            prefix,
+            self.0.title,
+            self.0.web_url,
+            self.responsible(user_map, backend),
+            age
+        )
+    }
+}
+
+/// Render a [`chrono::Duration`] as a short human-readable age, e.g. `"3d"`,
+/// `"5h"` or `"40m"`.
+fn format_age(age: chrono::Duration) -> String {
+    let hours = age.num_hours();
+    if hours < 1 {
+        format!("{}m", age.num_minutes().max(0))
+    } else if hours < 24 {
+        format!("{hours}h")
+    } else {
+        format!("{}d", hours / 24)
+    }
+}
+
+/// Render every in-scope MR as a single digest [`ChatMessage`]: a header
+/// stating how many MRs are awaiting merge, followed by one section per MR.
+fn render_digest(
+    mrs: &[WrappedMR],
+    now: chrono::DateTime<Local>,
+    user_map: &UserMap,
+    backend: ChatBackend,
+) -> ChatMessage {
+    let n = mrs.len();
+    let header = format!(
+        "{n} merge request{} awaiting merge",
+        if n == 1 { "" } else { "s" }
+    );
+    let mut message = ChatMessage::new(header.clone(), Some(header), None);
+    message.sections = mrs
+        .iter()
+        .map(|mr| mr.render_digest_section(now, user_map, backend))
+        .collect();
+    message
+}
+
 impl SlackMessageTemplate for WrappedMR {
     fn render_template(&self) -> SlackMessageContent {
-        // self.0.u
-        SlackMessageContent::new()
-            .with_text(format!(
-                "MR #{} awaiting merge: \"{}\"",
-                self.0.id, self.0.title
-            ))
-            .with_blocks(slack_blocks![
-                some_into(SlackSectionBlock::new().with_text(md!(format!(
-                    "MR **#{}: [{}]({})**",
-                    self.0.id, self.0.title, self.0.web_url
-                ))))
-            ])
+        let message = self.render(&UserMap::default(), ChatBackend::Slack);
+        let mut content = SlackMessageContent::new().with_text(message.text);
+        if let Some(markdown) = message.markdown {
+            content = content.with_blocks(slack_blocks![some_into(
+                SlackSectionBlock::new().with_text(md!(markdown))
+            )]);
+        }
+        content
     }
 }
 
+/// Minimal subset of a GitLab "Merge Request Hook" webhook payload. See
+/// <https://docs.gitlab.com/ee/user/project/integrations/webhook_events.html#merge-request-events>.
+#[derive(Debug, Clone, Deserialize)]
+struct MrHookPayload {
+    object_kind: String,
+    user: MrHookUser,
+    project: MrHookProject,
+    object_attributes: MrHookAttributes,
+    /// Per-field previous/current values for whatever changed in this event.
+    /// Only present on `update` actions; used to tell a draft→ready
+    /// transition apart from the many other edits that also fire `update`.
+    #[serde(default)]
+    changes: Option<MrHookChanges>,
+}
+
+/// The subset of GitLab's `changes` hash we care about: draft/WIP state,
+/// under whichever key the instance's GitLab version uses for it.
+#[derive(Debug, Clone, Deserialize)]
+struct MrHookChanges {
+    #[serde(default)]
+    work_in_progress: Option<MrHookValueChange<bool>>,
+    #[serde(default)]
+    draft: Option<MrHookValueChange<bool>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct MrHookValueChange<T> {
+    previous: T,
+    current: T,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct MrHookUser {
+    username: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct MrHookProject {
+    path_with_namespace: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct MrHookAttributes {
+    iid: u64,
+    target_project_id: u64,
+    title: String,
+    url: Url,
+    action: Option<String>,
+}
+
+/// Whether this webhook event represents the MR flipping out of draft/WIP
+/// (previous = true, current = false), as opposed to any other `update`
+/// event (title/description edits, new commits, label or assignee changes,
+/// etc.) which GitLab fires under the same `action: "update"`.
+fn became_ready_for_review(payload: &MrHookPayload) -> bool {
+    payload
+        .changes
+        .as_ref()
+        .and_then(|changes| changes.work_in_progress.as_ref().or(changes.draft.as_ref()))
+        .is_some_and(|change| change.previous && !change.current)
+}
+
+/// Render a webhook event into a backend-agnostic [`ChatMessage`]. Mirrors
+/// [`WrappedMR::render`], but works off the webhook payload fields directly
+/// since a full [`MergeRequest`] isn't available until we poll for it.
+fn render_hook_event(payload: &MrHookPayload, user_map: &UserMap, backend: ChatBackend) -> ChatMessage {
+    let author = user_map.mention(&payload.user.username, backend);
+    let prefix = format!("{} ", payload.project.path_with_namespace);
+    ChatMessage::new(
+        format!(
+            "{}MR #{} awaiting merge: \"{}\" (opened by {author})",
+            prefix, payload.object_attributes.iid, payload.object_attributes.title
+        ),
+        Some(format!(
+            "{}MR **#{}: [{}]({})** — opened by {author}",
+            prefix,
+            payload.object_attributes.iid,
+            payload.object_attributes.title,
+            payload.object_attributes.url
+        )),
+        Some(payload.object_attributes.url.to_string()),
+    )
+}
+
+/// Shared state handed to the Axum webhook handler.
+struct WebhookState {
+    webhook_secret: SecretString,
+    notifier: Option<Box<dyn ChatNotifier>>,
+    backend: ChatBackend,
+    user_map: UserMap,
+    gitlab: AsyncGitlab,
+    still_open_after: Duration,
+}
+
+/// Spawn a background task that re-checks an opened MR after the configured
+/// `--still-open-after-secs` delay and nags again if it is still open. Covers MRs that are
+/// opened and then never touched again, so no further GitLab event would
+/// otherwise fire.
+fn schedule_still_open_check(state: Arc<WebhookState>, payload: MrHookPayload) {
+    tokio::spawn(async move {
+        tokio::time::sleep(state.still_open_after).await;
+        let mr_q = merge_requests::MergeRequest::builder()
+            .project(payload.object_attributes.target_project_id)
+            .merge_request(payload.object_attributes.iid)
+            .build()
+            .unwrap();
+        let mr: Result<MergeRequest, _> = mr_q.query_async(&state.gitlab).await;
+        match mr {
+            Ok(mr)
+                if matches!(
+                    mr.state,
+                    gitlab::MergeRequestState::Opened | gitlab::MergeRequestState::Reopened
+                ) =>
+            {
+                if let Some(notifier) = &state.notifier {
+                    let wrapped = WrappedMR(mr);
+                    if let Err(err) = notifier
+                        .post(&[wrapped.render(&state.user_map, state.backend)])
+                        .await
+                    {
+                        debug!(?err, "failed to post still-open notification");
+                    }
+                }
+            }
+            Ok(mr) => trace!(mr_id=?mr.id, state=?mr.state, "MR no longer open at still-open check"),
+            Err(err) => debug!(?err, "failed to re-fetch MR for still-open check"),
+        }
+    });
+}
+
+/// Validate the `X-Gitlab-Token` header and, for relevant merge request
+/// state transitions (opened, reopened, moved out of draft), notify the
+/// configured chat backend.
+async fn handle_mr_hook(
+    State(state): State<Arc<WebhookState>>,
+    headers: HeaderMap,
+    Json(payload): Json<MrHookPayload>,
+) -> StatusCode {
+    let token_ok = headers
+        .get("X-Gitlab-Token")
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value == state.webhook_secret.expose_secret());
+    if !token_ok {
+        return StatusCode::UNAUTHORIZED;
+    }
+    if payload.object_kind != "merge_request" {
+        return StatusCode::OK;
+    }
+    let _span =
+        info_span!("webhook_merge_request_event", iid = payload.object_attributes.iid).entered();
+    let action = payload.object_attributes.action.as_deref().unwrap_or("");
+    let ready_for_review = action == "update" && became_ready_for_review(&payload);
+    if matches!(action, "open" | "reopen") || ready_for_review {
+        if let Some(notifier) = &state.notifier {
+            let message = render_hook_event(&payload, &state.user_map, state.backend);
+            if let Err(err) = notifier.post(&[message]).await {
+                debug!(?err, "failed to post webhook-triggered notification");
+            }
+        }
+        if matches!(action, "open" | "reopen") {
+            schedule_still_open_check(state.clone(), payload);
+        }
+    }
+    StatusCode::OK
+}
+
+/// Run `mr-nag` as a long-lived HTTP server that reacts to GitLab merge
+/// request webhook events instead of polling on a schedule. This avoids
+/// GitLab API rate pressure and gets notifications out near-real-time,
+/// fitting teams that would rather register a project hook once than
+/// schedule frequent cron polls.
+async fn run_webhook_server(
+    bind_addr: SocketAddr,
+    webhook_secret: SecretString,
+    still_open_after: Duration,
+    notifier: Option<Box<dyn ChatNotifier>>,
+    backend: ChatBackend,
+    user_map: UserMap,
+    gitlab: AsyncGitlab,
+) -> anyhow::Result<()> {
+    let state = Arc::new(WebhookState {
+        webhook_secret,
+        notifier,
+        backend,
+        user_map,
+        gitlab,
+        still_open_after,
+    });
+    let app = Router::new()
+        .route("/webhook", post(handle_mr_hook))
+        .with_state(state);
+    let listener = tokio::net::TcpListener::bind(bind_addr)
+        .await
+        .with_context(|| format!("failed to bind webhook listener on {bind_addr}"))?;
+    debug!(%bind_addr, "webhook server listening");
+    axum::serve(listener, app)
+        .await
+        .context("webhook server stopped unexpectedly")?;
+    Ok(())
+}
+
 fn setup_logging(args: &CmdArgs) -> anyhow::Result<()> {
     // First, setup LogTracer to catch events from the reqwest/gitlab crates
     let filter_layer = EnvFilter::try_from_default_env()
@@ -131,7 +804,36 @@ async fn main() -> anyhow::Result<()> {
         .build_async()
         .await
         .context("unable to build gitlab API client")?;
+    let (backend, notifier) = match build_notifier(&args) {
+        Some((backend, notifier)) => (backend, Some(notifier)),
+        None => (ChatBackend::Mattermost, None),
+    };
+    let user_map = match &args.user_map {
+        Some(path) => UserMap::load(path)?,
+        None => UserMap::default(),
+    };
+    if let Some(bind_addr) = args.serve {
+        let webhook_secret = args
+            .webhook_secret
+            .context("--webhook-secret is required when --serve is set")?;
+        return run_webhook_server(
+            bind_addr,
+            webhook_secret,
+            Duration::from_secs(args.still_open_after_secs),
+            notifier,
+            backend,
+            user_map,
+            gitlab,
+        )
+        .await;
+    }
+    if args.gitlab_project_id.is_none() && args.gitlab_group_id.is_none() {
+        anyhow::bail!(
+            "one of --gitlab-project-id or --gitlab-group-id is required when not using --serve"
+        );
+    }
     let now = Local::now();
+    let mut digest_mrs = Vec::new();
     for mr in get_merge_requsts(&args, &gitlab).await.unwrap() {
         let _mr_span = info_span!("processing_merge_request").entered();
         if let Some(dwell) = args.min_dwell_secs {
@@ -141,28 +843,48 @@ async fn main() -> anyhow::Result<()> {
                 continue;
             }
         }
+        if let Some(soak) = args.min_age_secs {
+            // Skip this MR and continue to next if it hasn't soaked since creation yet
+            if now.signed_duration_since(mr.created_at).num_seconds() < soak {
+                trace!(mr_id=?mr.id,created_at=?mr.created_at,soak,"skipping due to insufficient age since creation");
+                continue;
+            }
+        }
+        let wrapped = WrappedMR(mr);
+        if args.only_blocked && !wrapped.is_blocked() {
+            trace!(mr_id=?wrapped.0.id, "skipping MR that is not blocked by merge conflicts");
+            continue;
+        }
         let msg = format!(
-            "MR #{} ({}) is awaiting merge{}",
-            mr.id,
-            mr.title,
+            "{}MR #{} ({}) is awaiting merge{}{}",
+            wrapped
+                .project_path()
+                .map_or_else(String::new, |p| format!("{p} ")),
+            wrapped.0.id,
+            wrapped.0.title,
             match &args.target_branch {
                 None => ".".to_string(),
-                Some(tb) => format!(" to target branch: {}.", &tb),
+                Some(tb) => format!(" to target branch: {}.", &tb),
+            },
+            if wrapped.is_blocked() {
+                " [BLOCKED: needs rebase / has conflicts]"
+            } else {
+                ""
             }
         );
         // print the message to stdout
         println!("{msg}");
-        if let Some(hook_url) = args.slack_webhook_url.clone() {
-            let _slack_span = info_span!("slack_webhook_post").entered();
-            let client = SlackClient::new(SlackClientHyperConnector::new());
-
-            client
-                .post_webhook_message(
-                    &hook_url,
-                    &SlackApiPostWebhookMessageRequest::new(WrappedMR(mr).render_template()),
-                )
-                .await
-                .unwrap();
+        if args.digest {
+            digest_mrs.push(wrapped);
+        } else if let Some(notifier) = &notifier {
+            notifier.post(&[wrapped.render(&user_map, backend)]).await?;
+        }
+    }
+    if args.digest && !digest_mrs.is_empty() {
+        if let Some(notifier) = &notifier {
+            notifier
+                .post(&[render_digest(&digest_mrs, now, &user_map, backend)])
+                .await?;
         }
     }
     Ok(())